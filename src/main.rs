@@ -1,27 +1,41 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use anyhow::Result;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::char::{REPLACEMENT_CHARACTER, decode_utf16};
-use windows::Win32::UI::WindowsAndMessaging::{MB_ICONINFORMATION, MessageBoxW};
+use std::net::UdpSocket;
+use windows::Win32::Foundation::{COLORREF, RECT};
+use windows::Win32::UI::HiDpi::{
+    DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, GetDpiForWindow, SetProcessDpiAwarenessContext,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_RETURN;
+use windows::Win32::UI::WindowsAndMessaging::{
+    AppendMenuW, DT_CENTER, DT_SINGLELINE, DT_VCENTER, DrawTextW, EN_CHANGE, EN_KILLFOCUS,
+    EN_SETFOCUS, EnableWindow, GetClientRect, GetSystemMenu, InvalidateRect, MF_SEPARATOR,
+    MF_STRING, PostMessageW, WM_APP, WM_KEYDOWN, WM_LBUTTONDOWN, WM_SIZE, WM_SYSCOMMAND, WS_POPUP,
+};
 use windows::core::HSTRING;
 use windows::{
     Win32::{
         Foundation::{HWND, LPARAM, LRESULT, WPARAM},
         Graphics::Gdi::{
-            BeginPaint, CLIP_DEFAULT_PRECIS, COLOR_MENUBAR, CreateFontW, DEFAULT_CHARSET,
-            DEFAULT_QUALITY, EndPaint, FF_DONTCARE, GetSysColorBrush, HFONT, OUT_DEFAULT_PRECIS,
-            PAINTSTRUCT, SelectObject, SetBkMode, TRANSPARENT, TextOutW,
+            BeginPaint, CLIP_DEFAULT_PRECIS, COLOR_MENUBAR, CreateFontW, DC_BRUSH,
+            DEFAULT_CHARSET, DEFAULT_QUALITY, EndPaint, FF_DONTCARE, FillRect, FrameRect, GetDC,
+            GetStockObject, GetSysColorBrush, GetTextMetricsW, HBRUSH, HFONT, OUT_DEFAULT_PRECIS,
+            PAINTSTRUCT, ReleaseDC, SelectObject, SetBkMode, SetDCBrushColor, TEXTMETRICW,
+            TRANSPARENT,
         },
         UI::{
-            Input::KeyboardAndMouse::SetFocus,
+            Input::KeyboardAndMouse::{GetFocus, SetFocus},
             WindowsAndMessaging::{
-                CW_USEDEFAULT, CreateWindowExW, DefWindowProcW, DispatchMessageW, ES_CENTER,
-                ES_NUMBER, GetMessageW, HMENU, IDI_APPLICATION, IsDialogMessageW, LoadCursorW, MSG,
-                PostQuitMessage, RegisterClassW, SW_NORMAL, SendMessageW, ShowWindow,
-                TranslateMessage, WINDOW_EX_STYLE, WINDOW_STYLE, WM_COMMAND, WM_CREATE, WM_DESTROY,
-                WM_GETTEXT, WM_PAINT, WM_SETFONT, WNDCLASSW, WS_BORDER, WS_CAPTION, WS_CHILD,
-                WS_OVERLAPPED, WS_SYSMENU, WS_TABSTOP, WS_VISIBLE,
+                CW_USEDEFAULT, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW,
+                EM_SETLIMITTEXT, ES_CENTER, ES_NUMBER, GetMessageW, HMENU, IDI_APPLICATION,
+                IsDialogMessageW, LoadCursorW, MSG, PostQuitMessage, RegisterClassW, SW_NORMAL,
+                SWP_NOACTIVATE, SWP_NOZORDER, SendMessageW, SetWindowPos, ShowWindow,
+                TranslateMessage, WINDOW_EX_STYLE, WINDOW_STYLE, WM_COMMAND, WM_CREATE,
+                WM_DESTROY, WM_DPICHANGED, WM_GETTEXT, WM_PAINT, WM_SETFONT, WNDCLASSW, WS_BORDER,
+                WS_CAPTION, WS_CHILD, WS_MAXIMIZEBOX, WS_OVERLAPPED, WS_SYSMENU, WS_TABSTOP,
+                WS_THICKFRAME, WS_VISIBLE,
             },
         },
     },
@@ -29,15 +43,39 @@ use windows::{
 };
 
 const CLASS_NAME: PCWSTR = w!("iq-calc-window-class");
+const POPUP_CLASS_NAME: PCWSTR = w!("iq-calc-popup-class");
 const ID_EDIT: isize = 42;
 const ID_BUTTON: isize = 43;
 const TEXT_1: PCWSTR = w!("あなたの IQ を計算します。");
 const TEXT_2: PCWSTR = w!("あなたの IQ を入力してください。");
+const DEFAULT_SEND_ADDR: &str = "127.0.0.1:9876";
+const ID_ABOUT: usize = 0x1000;
+const HINT_TEXT: PCWSTR = w!("1〜300 の整数を入力してください");
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Calculator,
+    Display,
+}
 
 thread_local! {
     static FONT: Cell<HFONT> = Cell::default();
     static EDIT: Cell<HWND> = Cell::default();
     static BUTTON: Cell<HWND> = Cell::default();
+    static DPI: Cell<u32> = const { Cell::new(96) };
+    static MODE: Cell<Mode> = const { Cell::new(Mode::Calculator) };
+    static RECEIVE_PORT: Cell<u16> = const { Cell::new(0) };
+    static SEND_ADDR: RefCell<String> = RefCell::new(DEFAULT_SEND_ADDR.to_string());
+    static RECEIVED: RefCell<Option<String>> = const { RefCell::new(None) };
+    static POPUP_PARENT: Cell<HWND> = Cell::default();
+    static POPUP_PREV_FOCUS: Cell<HWND> = Cell::default();
+    static POPUP_TEXT: RefCell<String> = const { RefCell::new(String::new()) };
+    static EDIT_RECT: Cell<RECT> = Cell::default();
+    static SHOW_HINT: Cell<bool> = const { Cell::new(false) };
+}
+
+fn scale(v: i32, dpi: u32) -> i32 {
+    v * dpi as i32 / 96
 }
 
 unsafe extern "system" fn wnd_proc(
@@ -56,6 +94,29 @@ unsafe extern "system" fn wnd_proc(
         WM_COMMAND => {
             command(hwnd, wparam).ok();
         }
+        WM_DPICHANGED => {
+            dpi_changed(hwnd, wparam, lparam).ok();
+        }
+        WM_SIZE => {
+            if MODE.get() == Mode::Calculator {
+                layout(hwnd).ok();
+            }
+            unsafe { InvalidateRect(Some(hwnd), None, true) };
+        }
+        WM_APP => {
+            // Exactly one `PostMessageW(WM_APP, ...)` is sent per `Box::into_raw` in
+            // `spawn_receiver`; this is the single place that reclaims it.
+            let received = unsafe { Box::from_raw(lparam.0 as *mut String) };
+            RECEIVED.with_borrow_mut(|r| *r = Some(*received));
+            unsafe { InvalidateRect(Some(hwnd), None, true) };
+        }
+        WM_SYSCOMMAND => {
+            if wparam.0 & 0xFFF0 == ID_ABOUT {
+                show_about(hwnd).ok();
+            } else {
+                return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) };
+            }
+        }
         WM_DESTROY => unsafe {
             PostQuitMessage(0);
         },
@@ -65,20 +126,169 @@ unsafe extern "system" fn wnd_proc(
 }
 
 fn paint(hwnd: HWND) -> Result<()> {
+    match MODE.get() {
+        Mode::Calculator => paint_calculator(hwnd),
+        Mode::Display => paint_display(hwnd),
+    }
+}
+
+fn paint_calculator(hwnd: HWND) -> Result<()> {
+    let mut ps = PAINTSTRUCT::default();
+    let mut client = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut client)? };
+    let mid = client.top + (client.bottom - client.top) / 3;
+    let mut rect1 = RECT {
+        bottom: mid,
+        ..client
+    };
+    let mut rect2 = RECT { top: mid, ..client };
+    unsafe {
+        let hdc = BeginPaint(hwnd, &mut ps);
+        SelectObject(hdc, FONT.get().into());
+        SetBkMode(hdc, TRANSPARENT);
+        DrawTextW(hdc, &mut TEXT_1.as_wide().to_vec(), &mut rect1, DT_CENTER);
+        DrawTextW(hdc, &mut TEXT_2.as_wide().to_vec(), &mut rect2, DT_CENTER);
+        if SHOW_HINT.get() {
+            let edit_rect = EDIT_RECT.get();
+            let mut hint_rect = RECT {
+                left: client.left,
+                top: edit_rect.bottom + scale(4, DPI.get()),
+                right: client.right,
+                bottom: edit_rect.bottom + scale(20, DPI.get()),
+            };
+            DrawTextW(hdc, &mut HINT_TEXT.as_wide().to_vec(), &mut hint_rect, DT_CENTER);
+        }
+        EndPaint(hwnd, &ps).ok()?;
+    };
+    Ok(())
+}
+
+fn paint_display(hwnd: HWND) -> Result<()> {
     let mut ps = PAINTSTRUCT::default();
+    let mut client = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut client)? };
+    let text = RECEIVED.with_borrow(|r| r.clone()).unwrap_or_default();
+    let mut wide: Vec<u16> = HSTRING::from(text).as_wide().to_vec();
     unsafe {
         let hdc = BeginPaint(hwnd, &mut ps);
         SelectObject(hdc, FONT.get().into());
         SetBkMode(hdc, TRANSPARENT);
-        TextOutW(hdc, 10, 10, TEXT_1.as_wide()).ok()?;
-        TextOutW(hdc, 10, 30, TEXT_2.as_wide()).ok()?;
+        DrawTextW(
+            hdc,
+            &mut wide,
+            &mut client,
+            DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+        );
         EndPaint(hwnd, &ps).ok()?;
     };
     Ok(())
 }
 
+unsafe extern "system" fn popup_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            paint_popup(hwnd).ok();
+        }
+        WM_LBUTTONDOWN => {
+            dismiss_popup(hwnd).ok();
+        }
+        WM_KEYDOWN => {
+            if wparam.0 as u16 == VK_RETURN.0 {
+                dismiss_popup(hwnd).ok();
+            }
+        }
+        _ => return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    };
+    LRESULT::default()
+}
+
+fn size_down(rect: RECT, n: i32) -> RECT {
+    RECT {
+        left: rect.left + n,
+        top: rect.top + n,
+        right: rect.right - n,
+        bottom: rect.bottom - n,
+    }
+}
+
+fn paint_popup(hwnd: HWND) -> Result<()> {
+    let mut ps = PAINTSTRUCT::default();
+    let mut client = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut client)? };
+    unsafe {
+        let hdc = BeginPaint(hwnd, &mut ps);
+        let brush = HBRUSH(GetStockObject(DC_BRUSH).0 as _);
+
+        SetDCBrushColor(hdc, COLORREF(0x00F5F5F5));
+        FillRect(hdc, &client, brush);
+
+        let mut inner = size_down(client, scale(12, DPI.get()));
+        SetDCBrushColor(hdc, COLORREF(0x00804000));
+        FrameRect(hdc, &inner, brush);
+
+        SelectObject(hdc, FONT.get().into());
+        SetBkMode(hdc, TRANSPARENT);
+        let popup_text = POPUP_TEXT.with_borrow(|t| t.clone());
+        let mut text = HSTRING::from(popup_text).as_wide().to_vec();
+        DrawTextW(
+            hdc,
+            &mut text,
+            &mut inner,
+            DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+        );
+
+        EndPaint(hwnd, &ps).ok()?;
+    };
+    Ok(())
+}
+
+fn show_popup(parent: HWND, text: &str) -> Result<()> {
+    POPUP_TEXT.with_borrow_mut(|t| *t = text.to_string());
+    POPUP_PARENT.set(parent);
+    POPUP_PREV_FOCUS.set(unsafe { GetFocus() });
+    unsafe { EnableWindow(parent, false) };
+    let dpi = DPI.get();
+    let popup = unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            POPUP_CLASS_NAME,
+            w!("結果"),
+            WS_POPUP | WS_VISIBLE | WS_BORDER,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            scale(280, dpi),
+            scale(140, dpi),
+            Some(parent),
+            None,
+            None,
+            None,
+        )?
+    };
+    unsafe { SetFocus(Some(popup))? };
+    Ok(())
+}
+
+fn show_about(hwnd: HWND) -> Result<()> {
+    show_popup(hwnd, "IQ 計算機 v1.0 - zxrs")
+}
+
+fn dismiss_popup(hwnd: HWND) -> Result<()> {
+    let parent = POPUP_PARENT.get();
+    unsafe {
+        EnableWindow(parent, true);
+        DestroyWindow(hwnd)?;
+        SetFocus(Some(POPUP_PREV_FOCUS.get()))?;
+    }
+    Ok(())
+}
+
 fn get_edit_string(hwnd: HWND) -> String {
-    let mut buf = [0u16; 4];
+    let mut buf = [0u16; 8];
     unsafe {
         SendMessageW(
             hwnd,
@@ -101,25 +311,126 @@ fn decode(source: &[u16]) -> String {
 
 fn command(hwnd: HWND, wparam: WPARAM) -> Result<()> {
     let id = loword(wparam.0 as u32) as isize;
+    let code = hiword(wparam.0 as u32) as u32;
     if id == ID_BUTTON {
         let iq = get_edit_string(EDIT.get());
-        unsafe {
-            MessageBoxW(
-                Some(hwnd),
-                &HSTRING::from(format!("あなたの IQ は {iq} です！")),
-                w!("結果"),
-                MB_ICONINFORMATION,
-            )
-        };
-        unsafe { SetFocus(Some(BUTTON.get()))? };
+        send_value(&iq);
+        show_popup(hwnd, &format!("あなたの IQ は {iq} です！"))?;
+    } else if id == ID_EDIT {
+        match code {
+            EN_CHANGE => edit_changed(),
+            EN_KILLFOCUS => edit_focus_changed(hwnd, true),
+            EN_SETFOCUS => edit_focus_changed(hwnd, false),
+            _ => {}
+        }
     }
     Ok(())
 }
 
+fn is_valid_iq(text: &str) -> bool {
+    text.parse::<u32>().is_ok_and(|v| (1..=300).contains(&v))
+}
+
+fn edit_changed() {
+    let enabled = is_valid_iq(&get_edit_string(EDIT.get()));
+    unsafe { _ = EnableWindow(BUTTON.get(), enabled) };
+}
+
+fn edit_focus_changed(hwnd: HWND, killed: bool) {
+    SHOW_HINT.set(killed && !is_valid_iq(&get_edit_string(EDIT.get())));
+    unsafe { InvalidateRect(Some(hwnd), None, true) };
+}
+
+fn send_value(text: &str) {
+    let addr = SEND_ADDR.with_borrow(|a| a.clone());
+    if let Ok(socket) = UdpSocket::bind("0.0.0.0:0") {
+        let _ = socket.send_to(text.as_bytes(), addr);
+    }
+}
+
 fn create(hwnd: HWND) -> Result<()> {
+    DPI.set(unsafe { GetDpiForWindow(hwnd) });
     create_font();
-    create_edit(hwnd)?;
-    create_button(hwnd)?;
+    match MODE.get() {
+        Mode::Calculator => {
+            create_edit(hwnd)?;
+            create_button(hwnd)?;
+            layout(hwnd)?;
+            edit_changed();
+        }
+        Mode::Display => spawn_receiver(hwnd, RECEIVE_PORT.get()),
+    }
+    Ok(())
+}
+
+fn spawn_receiver(hwnd: HWND, port: u16) {
+    let hwnd = hwnd.0 as isize;
+    std::thread::spawn(move || -> Result<()> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        let mut buf = [0u8; 4096];
+        loop {
+            let (len, _) = socket.recv_from(&mut buf)?;
+            let text = String::from_utf8_lossy(&buf[..len]).into_owned();
+            let ptr = Box::into_raw(Box::new(text));
+            let hwnd = HWND(hwnd as *mut _);
+            unsafe { PostMessageW(Some(hwnd), WM_APP, WPARAM(0), LPARAM(ptr as isize))? };
+        }
+    });
+}
+
+fn layout(hwnd: HWND) -> Result<()> {
+    let mut client = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut client)? };
+    let width = client.right - client.left;
+    let height = client.bottom - client.top;
+
+    let mut tm = TEXTMETRICW::default();
+    unsafe {
+        let hdc = GetDC(Some(hwnd));
+        SelectObject(hdc, FONT.get().into());
+        GetTextMetricsW(hdc, &mut tm)?;
+        ReleaseDC(Some(hwnd), hdc);
+    }
+    let char_width = tm.tmAveCharWidth;
+    let char_height = tm.tmHeight;
+
+    let edit_w = (char_width * 8).max(width / 4);
+    let edit_h = char_height + char_height / 2;
+    let edit_x = (width - edit_w) / 2;
+    let edit_y = height / 2 - edit_h;
+
+    let button_w = (char_width * 10).max(width / 3);
+    let button_h = char_height + char_height;
+    let button_x = (width - button_w) / 2;
+    let button_y = edit_y + edit_h + char_height + char_height / 2;
+
+    EDIT_RECT.set(RECT {
+        left: edit_x,
+        top: edit_y,
+        right: edit_x + edit_w,
+        bottom: edit_y + edit_h,
+    });
+
+    unsafe {
+        SetWindowPos(
+            EDIT.get(),
+            None,
+            edit_x,
+            edit_y,
+            edit_w,
+            edit_h,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+        )?;
+        SetWindowPos(
+            BUTTON.get(),
+            None,
+            button_x,
+            button_y,
+            button_w,
+            button_h,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+        )?;
+    }
     Ok(())
 }
 
@@ -128,9 +439,14 @@ fn set_font(hwnd: HWND) {
 }
 
 fn create_font() {
+    let dpi = DPI.get();
+    let base_height = match MODE.get() {
+        Mode::Calculator => 18,
+        Mode::Display => 72,
+    };
     let font = unsafe {
         CreateFontW(
-            18,
+            scale(base_height, dpi),
             0,
             0,
             0,
@@ -161,10 +477,10 @@ fn create_edit(hwnd: HWND) -> Result<()> {
                 | WS_TABSTOP
                 | WINDOW_STYLE(ES_NUMBER as _)
                 | WINDOW_STYLE(ES_CENTER as _),
-            210,
-            28,
-            50,
-            22,
+            0,
+            0,
+            0,
+            0,
             Some(hwnd),
             Some(HMENU(ID_EDIT as _)),
             None,
@@ -173,6 +489,7 @@ fn create_edit(hwnd: HWND) -> Result<()> {
     };
     EDIT.set(hwnd);
     set_font(hwnd);
+    unsafe { SendMessageW(hwnd, EM_SETLIMITTEXT, Some(WPARAM(3)), None) };
     unsafe { SetFocus(Some(hwnd))? };
     Ok(())
 }
@@ -184,10 +501,10 @@ fn create_button(hwnd: HWND) -> Result<()> {
             w!("BUTTON"),
             w!("計算"),
             WS_VISIBLE | WS_CHILD | WS_TABSTOP,
-            80,
-            70,
-            120,
-            25,
+            0,
+            0,
+            0,
+            0,
             Some(hwnd),
             Some(HMENU(ID_BUTTON as _)),
             None,
@@ -199,7 +516,62 @@ fn create_button(hwnd: HWND) -> Result<()> {
     Ok(())
 }
 
+fn dpi_changed(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> Result<()> {
+    let dpi = hiword(wparam.0 as u32) as u32;
+    let suggested = unsafe { &*(lparam.0 as *const RECT) };
+    unsafe {
+        SetWindowPos(
+            hwnd,
+            None,
+            suggested.left,
+            suggested.top,
+            suggested.right - suggested.left,
+            suggested.bottom - suggested.top,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+        )?;
+    }
+    DPI.set(dpi);
+    create_font();
+    match MODE.get() {
+        Mode::Calculator => {
+            set_font(EDIT.get());
+            set_font(BUTTON.get());
+            layout(hwnd)?;
+        }
+        Mode::Display => unsafe {
+            InvalidateRect(Some(hwnd), None, true);
+        },
+    }
+    Ok(())
+}
+
+fn parse_args() {
+    let args: Vec<String> = std::env::args().collect();
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--receive" => {
+                i += 1;
+                if let Some(port) = args.get(i).and_then(|s| s.parse().ok()) {
+                    RECEIVE_PORT.set(port);
+                    MODE.set(Mode::Display);
+                }
+            }
+            "--send" => {
+                i += 1;
+                if let Some(addr) = args.get(i) {
+                    SEND_ADDR.with_borrow_mut(|a| *a = addr.clone());
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
 fn main() -> Result<()> {
+    parse_args();
+    unsafe { SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2)? };
     let wc = WNDCLASSW {
         lpfnWndProc: Some(wnd_proc),
         lpszClassName: CLASS_NAME,
@@ -208,12 +580,23 @@ fn main() -> Result<()> {
         ..Default::default()
     };
     unsafe { RegisterClassW(&wc) };
+    let popup_wc = WNDCLASSW {
+        lpfnWndProc: Some(popup_wnd_proc),
+        lpszClassName: POPUP_CLASS_NAME,
+        hCursor: unsafe { LoadCursorW(None, IDI_APPLICATION)? },
+        ..Default::default()
+    };
+    unsafe { RegisterClassW(&popup_wc) };
+    let title = match MODE.get() {
+        Mode::Calculator => w!("IQ 計算機"),
+        Mode::Display => w!("IQ 表示"),
+    };
     let hwnd = unsafe {
         CreateWindowExW(
             WINDOW_EX_STYLE::default(),
             CLASS_NAME,
-            w!("IQ 計算機"),
-            WS_OVERLAPPED | WS_CAPTION | WS_SYSMENU,
+            title,
+            WS_OVERLAPPED | WS_CAPTION | WS_SYSMENU | WS_THICKFRAME | WS_MAXIMIZEBOX,
             CW_USEDEFAULT,
             CW_USEDEFAULT,
             300,
@@ -224,6 +607,11 @@ fn main() -> Result<()> {
             None,
         )?
     };
+    unsafe {
+        let system_menu = GetSystemMenu(hwnd, false);
+        AppendMenuW(system_menu, MF_SEPARATOR, 0, None)?;
+        AppendMenuW(system_menu, MF_STRING, ID_ABOUT, w!("バージョン情報"))?;
+    }
     _ = unsafe { ShowWindow(hwnd, SW_NORMAL) };
     let mut msg = MSG::default();
     loop {
@@ -242,3 +630,8 @@ fn main() -> Result<()> {
 fn loword(l: u32) -> u16 {
     (l & 0xffff) as u16
 }
+
+#[inline]
+fn hiword(l: u32) -> u16 {
+    ((l >> 16) & 0xffff) as u16
+}